@@ -0,0 +1,31 @@
+//! Async pacing, enabled by the `tokio` feature.
+//!
+//! These methods sleep via [`tokio::time::sleep`], which only ever advances
+//! with real wall-clock time. They're therefore only implemented for
+//! [`RateLimiter<SystemClock>`] — offering them for an arbitrary injected
+//! [`Clock`] (e.g. [`FakeClock`](crate::FakeClock)) would let a limiter whose
+//! clock never advances on its own wait forever.
+
+use crate::{RateLimiter, SystemClock};
+
+impl RateLimiter<SystemClock> {
+    /// Waits, sleeping as needed, until this limiter would accept another
+    /// call.
+    pub async fn ready(&mut self) {
+        while let Some(wait) = self.time_until_ready() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Waits until ready, then runs `f`, returning its value.
+    ///
+    /// Unlike [`try_run`](RateLimiter::try_run), this never drops work: it
+    /// paces the caller by sleeping instead of rejecting the call. Useful in
+    /// async request loops where you want to submit as fast as allowed
+    /// rather than dropping work.
+    pub async fn run_after_ready<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        self.ready().await;
+        self.try_run(f)
+            .expect("ready() guarantees capacity is available immediately after it resolves")
+    }
+}
@@ -0,0 +1,74 @@
+//! Pluggable time sources for [`RateLimiter`](crate::RateLimiter).
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A source of the current time.
+///
+/// Implement this to drive a [`RateLimiter`](crate::RateLimiter) from
+/// something other than the system clock, e.g. [`FakeClock`] in tests.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+/// The real system clock, backed by [`Instant::now`].
+///
+/// This is the default [`Clock`] used by [`RateLimiter::new`](crate::RateLimiter::new)
+/// and friends.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A manually advanced clock for deterministic tests.
+///
+/// Cloning a `FakeClock` shares the same underlying time, so a clone kept by
+/// the test can [`advance`](FakeClock::advance) the clock handed to the
+/// `RateLimiter` under test.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ratelim::{Clock, FakeClock, RateLimiter};
+/// let clock = FakeClock::new();
+/// let mut lim = RateLimiter::with_clock(Duration::from_millis(50), clock.clone());
+///
+/// assert!(lim.try_run(|| ()).is_ok()); // first call is always free
+/// assert!(lim.try_run(|| ()).is_err()); // still hot
+///
+/// clock.advance(Duration::from_millis(50));
+/// assert!(lim.try_run(|| ()).is_ok()); // cooldown elapsed, no sleeping required
+/// ```
+#[derive(Debug, Clone)]
+pub struct FakeClock(Rc<Cell<Instant>>);
+
+impl FakeClock {
+    /// Creates a clock starting at the current time.
+    pub fn new() -> Self {
+        Self(Rc::new(Cell::new(Instant::now())))
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
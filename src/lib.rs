@@ -22,82 +22,340 @@
 //! # }
 //! ```
 
+use std::collections::VecDeque;
+use std::fmt;
 use std::time::{Duration, Instant};
 
-/// Allows to [run] the operation at most once per the cooldown period.
+mod clock;
+mod throttle;
+mod timer;
+#[cfg(feature = "tokio")]
+mod async_ext;
+
+pub use clock::{Clock, FakeClock, SystemClock};
+pub use throttle::{ThrottledReader, ThrottledWriter};
+pub use timer::Timer;
+
+/// The limiting strategy backing a [`RateLimiter`].
+#[derive(Debug, Clone)]
+enum Strategy {
+    /// At most one successful call per `cooldown`.
+    Cooldown {
+        cooldown: Duration,
+        start: Option<Instant>,
+    },
+    /// At most `count` successful calls within any window of `period`,
+    /// tracked via a ring of the timestamps of the calls still inside
+    /// the window.
+    Quota {
+        count: usize,
+        period: Duration,
+        timestamps: VecDeque<Instant>,
+    },
+    /// Generic cell rate algorithm: paces calls to one per `interval`,
+    /// while allowing a burst of up to `burst` calls by tolerating up to
+    /// `tau` of backlog in the theoretical arrival time (`tat`).
+    Gcra {
+        interval: Duration,
+        tau: Duration,
+        tat: Option<Instant>,
+    },
+}
+
+/// Allows to [run] the operation at most once per the cooldown period
+/// (or, under [`with_quota`], at most `count` times per sliding window;
+/// or, under [`with_gcra`], paced smoothly with a configurable burst).
 ///
 /// [run]: RateLimiter::run
+/// [`with_quota`]: RateLimiter::with_quota
+/// [`with_gcra`]: RateLimiter::with_gcra
 #[derive(Debug, Clone)]
-pub struct RateLimiter {
-    cooldown: Duration,
-    start: Option<Instant>,
+pub struct RateLimiter<C: Clock = SystemClock> {
+    strategy: Strategy,
+    clock: C,
+    dropped: u64,
+}
+
+/// A snapshot of how many calls a [`RateLimiter`] has denied, as returned by
+/// [`RateLimiter::take_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RateLimitStats {
+    dropped: u64,
+}
+
+impl RateLimitStats {
+    /// Returns the number of calls denied since the previous snapshot.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl fmt::Display for RateLimitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} dropped calls", self.dropped)
+    }
 }
 
-impl RateLimiter {
-    /// Creates a rate limiter with the given cooldown period.
+impl RateLimiter<SystemClock> {
+    /// Creates a rate limiter with the given cooldown period, driven by the
+    /// system clock.
     ///
     /// # Panics
     ///
     /// Panics if `cooldown.is_zero()`.
     pub fn new(cooldown: Duration) -> Self {
+        Self::with_clock(cooldown, SystemClock)
+    }
+
+    /// Creates a rate limiter that allows at most `count` successful calls
+    /// within any sliding window of `period`, rather than a single hard
+    /// cooldown. Memory use is bounded: at most `count` timestamps are
+    /// retained regardless of call rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count == 0` or `period.is_zero()`.
+    pub fn with_quota(count: usize, period: Duration) -> Self {
+        Self::with_quota_and_clock(count, period, SystemClock)
+    }
+
+    /// Creates a rate limiter that paces calls using the generic cell rate
+    /// algorithm (GCRA): on average at most `count` calls per `period`, but
+    /// allowing a burst of up to `burst` calls back-to-back before pacing
+    /// kicks in. Unlike [`with_quota`](Self::with_quota), this only stores a
+    /// single timestamp (the theoretical arrival time) regardless of `burst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count == 0`, `period.is_zero()`, or `burst == 0`.
+    pub fn with_gcra(count: usize, period: Duration, burst: usize) -> Self {
+        Self::with_gcra_and_clock(count, period, burst, SystemClock)
+    }
+}
+
+impl<C: Clock> RateLimiter<C> {
+    /// Creates a rate limiter with the given cooldown period, driven by a
+    /// custom [`Clock`]. Use this (with [`FakeClock`]) to test cooldown
+    /// boundaries deterministically instead of sleeping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cooldown.is_zero()`.
+    pub fn with_clock(cooldown: Duration, clock: C) -> Self {
         assert!(!cooldown.is_zero());
         Self {
-            cooldown,
-            start: None,
+            strategy: Strategy::Cooldown {
+                cooldown,
+                start: None,
+            },
+            clock,
+            dropped: 0,
+        }
+    }
+
+    /// Creates a quota-mode rate limiter (see [`with_quota`](RateLimiter::with_quota))
+    /// driven by a custom [`Clock`]. Use this (with [`FakeClock`]) to test
+    /// eviction boundaries deterministically instead of sleeping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count == 0` or `period.is_zero()`.
+    pub fn with_quota_and_clock(count: usize, period: Duration, clock: C) -> Self {
+        assert!(count > 0);
+        assert!(!period.is_zero());
+        Self {
+            strategy: Strategy::Quota {
+                count,
+                period,
+                timestamps: VecDeque::with_capacity(count),
+            },
+            clock,
+            dropped: 0,
+        }
+    }
+
+    /// Creates a GCRA-mode rate limiter (see [`with_gcra`](RateLimiter::with_gcra))
+    /// driven by a custom [`Clock`]. Use this (with [`FakeClock`]) to test
+    /// burst and pacing behavior deterministically instead of sleeping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count == 0`, `period.is_zero()`, or `burst == 0`.
+    pub fn with_gcra_and_clock(count: usize, period: Duration, burst: usize, clock: C) -> Self {
+        assert!(count > 0);
+        assert!(!period.is_zero());
+        assert!(burst > 0);
+        let interval = period / count as u32;
+        let tau = interval * (burst as u32 - 1);
+        Self {
+            strategy: Strategy::Gcra {
+                interval,
+                tau,
+                tat: None,
+            },
+            clock,
+            dropped: 0,
         }
     }
 
     /// Returns the cooldown period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this limiter was created with [`with_quota`](RateLimiter::with_quota)
+    /// or [`with_gcra`](RateLimiter::with_gcra).
     pub fn cooldown_period(&self) -> Duration {
-        self.cooldown
+        match self.strategy {
+            Strategy::Cooldown { cooldown, .. } => cooldown,
+            Strategy::Quota { .. } | Strategy::Gcra { .. } => {
+                panic!("cooldown_period() only applies to cooldown-mode limiters")
+            }
+        }
     }
 
     /// (Re)starts the cooldown period.
     /// Returns the previous start time if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this limiter was created with [`with_quota`](RateLimiter::with_quota)
+    /// or [`with_gcra`](RateLimiter::with_gcra).
     pub fn start_now(&mut self) -> Option<Instant> {
-        self.start.replace(Instant::now())
+        let now = self.clock.now();
+        match &mut self.strategy {
+            Strategy::Cooldown { start, .. } => start.replace(now),
+            Strategy::Quota { .. } | Strategy::Gcra { .. } => {
+                panic!("start_now() only applies to cooldown-mode limiters")
+            }
+        }
     }
 
-    /// Runs the function if the cooldown period has elapsed.
+    /// Runs the function if the cooldown period has elapsed, returning its
+    /// value. Otherwise returns `None` without running it.
     ///
     /// The first call succeeds immediately, starting the `RateLimiter`.
-    pub fn run(&mut self, f: impl FnOnce()) {
-        self.try_run(f).ok();
+    pub fn run<R>(&mut self, f: impl FnOnce() -> R) -> Option<R> {
+        self.try_run(f).ok()
+    }
+
+    /// Returns how long until this limiter would accept another call, or
+    /// `None` if it would accept one right now. Unlike [`try_run`], this
+    /// never runs anything and never advances the limiter's state.
+    ///
+    /// [`try_run`]: RateLimiter::try_run
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    pub(crate) fn time_until_ready(&self) -> Option<Duration> {
+        let now = self.clock.now();
+        match &self.strategy {
+            Strategy::Cooldown { cooldown, start } => {
+                let start_at = (*start)?;
+                let t_cold = start_at + *cooldown;
+                (now < t_cold).then(|| t_cold - now)
+            }
+            Strategy::Quota {
+                count,
+                period,
+                timestamps,
+            } => {
+                let mut active = timestamps.iter().copied().filter(|&t| now.duration_since(t) < *period);
+                let oldest = active.next();
+                let active_count = oldest.is_some() as usize + active.count();
+                (active_count >= *count).then(|| oldest.unwrap() + *period - now)
+            }
+            Strategy::Gcra { tau, tat, .. } => {
+                let new_tat = tat.unwrap_or(now).max(now);
+                let backlog = new_tat.saturating_duration_since(now);
+                (backlog > *tau).then(|| backlog - *tau)
+            }
+        }
     }
 
-    /// Runs the function if the cooldown period has elapsed.
-    /// Otherwise errs with the time remaining.
+    /// Returns the number of calls denied since the last [`take_stats`](Self::take_stats).
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the current drop statistics and resets the counter to zero.
+    pub fn take_stats(&mut self) -> RateLimitStats {
+        RateLimitStats {
+            dropped: std::mem::take(&mut self.dropped),
+        }
+    }
+
+    /// Runs the function if the cooldown period has elapsed, yielding its
+    /// value. Otherwise errs with the time remaining.
     ///
     /// The first call succeeds immediately, starting the `RateLimiter`.
-    pub fn try_run(&mut self, f: impl FnOnce()) -> Result<(), Duration> {
-        let Some(start) = self.start else {
-            f();
-            self.start_now();
-            return Ok(());
-        };
+    pub fn try_run<R>(&mut self, f: impl FnOnce() -> R) -> Result<R, Duration> {
+        let now = self.clock.now();
+        let result = match &mut self.strategy {
+            Strategy::Cooldown { cooldown, start } => {
+                let Some(start_at) = *start else {
+                    let value = f();
+                    *start = Some(now);
+                    return Ok(value);
+                };
 
-        let t_cold = start + self.cooldown;
-        let now = Instant::now();
-        if now < t_cold {
-            //
-            //   |<------ cooldown_period ----->|
-            // --+---------------+--------------+---------------> time
-            //   |<-- elapsed -->|<--- wait --->|
-            //   |               |              |
-            //   start           now            t_cold
-            //
-            Err(t_cold - now)
-        } else {
-            //
-            //   |<----------------- elapsed ------------------->|
-            //   |<------ cooldown_period ----->|<-- overshot -->|
-            // --+------------------------------+----------------+----> time
-            //   |                              |                |
-            //   start                          t_cold           now
-            //
-            f();
-            self.start.replace(now);
-            Ok(())
+                let t_cold = start_at + *cooldown;
+                if now < t_cold {
+                    //
+                    //   |<------ cooldown_period ----->|
+                    // --+---------------+--------------+---------------> time
+                    //   |<-- elapsed -->|<--- wait --->|
+                    //   |               |              |
+                    //   start           now            t_cold
+                    //
+                    Err(t_cold - now)
+                } else {
+                    //
+                    //   |<----------------- elapsed ------------------->|
+                    //   |<------ cooldown_period ----->|<-- overshot -->|
+                    // --+------------------------------+----------------+----> time
+                    //   |                              |                |
+                    //   start                          t_cold           now
+                    //
+                    let value = f();
+                    *start = Some(now);
+                    Ok(value)
+                }
+            }
+            Strategy::Quota {
+                count,
+                period,
+                timestamps,
+            } => {
+                while let Some(&oldest) = timestamps.front() {
+                    if now.duration_since(oldest) >= *period {
+                        timestamps.pop_front();
+                    } else {
+                        break;
+                    }
+                }
+
+                if timestamps.len() < *count {
+                    let value = f();
+                    timestamps.push_back(now);
+                    Ok(value)
+                } else {
+                    let oldest = *timestamps.front().expect("count > 0, so quota full implies an entry");
+                    Err(oldest + *period - now)
+                }
+            }
+            Strategy::Gcra { interval, tau, tat } => {
+                let new_tat = tat.unwrap_or(now).max(now);
+                let backlog = new_tat.saturating_duration_since(now);
+                if backlog <= *tau {
+                    let value = f();
+                    *tat = Some(new_tat + *interval);
+                    Ok(value)
+                } else {
+                    Err(backlog - *tau)
+                }
+            }
+        };
+        if result.is_err() {
+            self.dropped += 1;
         }
+        result
     }
 }
@@ -0,0 +1,134 @@
+//! Bandwidth-throttled I/O adapters.
+//!
+//! Unlike [`RateLimiter`](crate::RateLimiter), which limits how often an
+//! operation may run, [`ThrottledReader`] and [`ThrottledWriter`] limit how
+//! many bytes per second pass through a [`Read`]/[`Write`], which is useful
+//! for paced uploads or log shipping.
+//!
+//! These always run on the real system clock: when the budget is exhausted
+//! they block the current thread with [`thread::sleep`], which (unlike
+//! [`RateLimiter`](crate::RateLimiter)) gives no way to honor an injected
+//! [`Clock`](crate::Clock) such as `FakeClock` — a clock that doesn't
+//! advance on its own would block forever.
+
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A token bucket of bytes, refilling continuously at `bytes_per_sec`.
+struct ByteBudget {
+    bytes_per_sec: u64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl ByteBudget {
+    fn new(bytes_per_sec: u64) -> Self {
+        assert!(bytes_per_sec > 0);
+        Self {
+            bytes_per_sec,
+            tokens: bytes_per_sec as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated);
+        let cap = self.bytes_per_sec as f64;
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * cap).min(cap);
+        self.updated = now;
+    }
+
+    /// Blocks until at least one byte of budget is available, then consumes
+    /// and returns how many of the `wanted` bytes may pass right now.
+    fn acquire(&mut self, wanted: usize) -> usize {
+        if wanted == 0 {
+            return 0;
+        }
+        loop {
+            self.refill();
+            if self.tokens >= 1.0 {
+                let allowed = (self.tokens.floor() as usize).min(wanted).max(1);
+                self.tokens -= allowed as f64;
+                return allowed;
+            }
+            let wait = Duration::from_secs_f64((1.0 - self.tokens) / self.bytes_per_sec as f64);
+            thread::sleep(wait);
+        }
+    }
+}
+
+/// Wraps a [`Read`], capping throughput to a configured bytes-per-second
+/// budget. Each [`read`](Read::read) performs a short read of at most the
+/// bytes currently available in the budget, blocking first if the budget is
+/// exhausted.
+pub struct ThrottledReader<R> {
+    inner: R,
+    budget: ByteBudget,
+}
+
+impl<R> ThrottledReader<R> {
+    /// Wraps `inner`, capping reads to `bytes_per_sec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec == 0`.
+    pub fn new(inner: R, bytes_per_sec: u64) -> Self {
+        Self {
+            inner,
+            budget: ByteBudget::new(bytes_per_sec),
+        }
+    }
+
+    /// Returns the wrapped reader, discarding the throttle.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let allowed = self.budget.acquire(buf.len());
+        self.inner.read(&mut buf[..allowed])
+    }
+}
+
+/// Wraps a [`Write`], capping throughput to a configured bytes-per-second
+/// budget. Each [`write`](Write::write) performs a short write of at most the
+/// bytes currently available in the budget, blocking first if the budget is
+/// exhausted.
+pub struct ThrottledWriter<W> {
+    inner: W,
+    budget: ByteBudget,
+}
+
+impl<W> ThrottledWriter<W> {
+    /// Wraps `inner`, capping writes to `bytes_per_sec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec == 0`.
+    pub fn new(inner: W, bytes_per_sec: u64) -> Self {
+        Self {
+            inner,
+            budget: ByteBudget::new(bytes_per_sec),
+        }
+    }
+
+    /// Returns the wrapped writer, discarding the throttle.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ThrottledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let allowed = self.budget.acquire(buf.len());
+        self.inner.write(&buf[..allowed])
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,29 @@
+//! A tiny RAII stopwatch.
+
+use std::time::{Duration, Instant};
+
+/// Measures the time between its creation and when it is dropped, then
+/// reports the elapsed duration to a callback.
+pub struct Timer<F: FnOnce(Duration)> {
+    start: Instant,
+    on_drop: Option<F>,
+}
+
+impl<F: FnOnce(Duration)> Timer<F> {
+    /// Starts the stopwatch. `on_drop` runs once, when the `Timer` is dropped,
+    /// with the elapsed time.
+    pub fn start(on_drop: F) -> Self {
+        Self {
+            start: Instant::now(),
+            on_drop: Some(on_drop),
+        }
+    }
+}
+
+impl<F: FnOnce(Duration)> Drop for Timer<F> {
+    fn drop(&mut self) {
+        if let Some(on_drop) = self.on_drop.take() {
+            on_drop(self.start.elapsed());
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use std::io::{Read, Write};
 use std::{thread, time::Duration};
 
-use ratelim::{RateLimiter, Timer};
+use ratelim::{FakeClock, RateLimiter, ThrottledReader, ThrottledWriter, Timer};
 
 #[test]
 fn test_runner() {
@@ -26,8 +27,139 @@ fn test_runner() {
     let _ = lim.clone();
 }
 
+#[test]
+fn test_run_returns_closure_value() {
+    let mut lim = RateLimiter::new(Duration::from_millis(50));
+
+    // Cold: the closure runs and its value comes back through Ok/Some.
+    assert_eq!(lim.try_run(|| 42), Ok(42));
+    assert_eq!(lim.run(|| "x"), None); // hot; run() swallows the Err(wait)
+
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(lim.run(|| "x"), Some("x"));
+}
+
+#[test]
+fn test_quota_eviction_boundary() {
+    let clock = FakeClock::new();
+    let period = Duration::from_millis(100);
+    let mut lim = RateLimiter::with_quota_and_clock(2, period, clock.clone());
+
+    // Burst of 2 is allowed immediately.
+    assert!(lim.try_run(|| ()).is_ok());
+    clock.advance(Duration::from_millis(40));
+    assert!(lim.try_run(|| ()).is_ok());
+
+    // A 3rd call is rejected while both timestamps are still in the window.
+    let wait = lim.try_run(|| unreachable!()).unwrap_err();
+    assert_eq!(wait, Duration::from_millis(60)); // oldest timestamp expires at t=100ms, now at t=40ms
+
+    // Just before the oldest timestamp expires, still rejected.
+    clock.advance(wait - Duration::from_millis(1));
+    assert!(lim.try_run(|| unreachable!()).is_err());
+
+    // Once the oldest timestamp is exactly `period` old, it's evicted and a
+    // slot frees up.
+    clock.advance(Duration::from_millis(1));
+    assert!(lim.try_run(|| ()).is_ok());
+}
+
+#[test]
+fn test_gcra_burst_then_pace() {
+    let clock = FakeClock::new();
+    // 2 calls/sec, burst of 3: interval = 500ms, tau = 500ms * 2 = 1000ms.
+    let mut lim = RateLimiter::with_gcra_and_clock(2, Duration::from_secs(1), 3, clock.clone());
+
+    // The whole burst of 3 is allowed back-to-back.
+    assert!(lim.try_run(|| ()).is_ok());
+    assert!(lim.try_run(|| ()).is_ok());
+    assert!(lim.try_run(|| ()).is_ok());
+
+    // The burst is now exhausted; the 4th call must wait out the pacing
+    // interval before it's allowed.
+    let wait = lim.try_run(|| unreachable!()).unwrap_err();
+    assert_eq!(wait, Duration::from_millis(500));
+
+    // Just shy of the wait, still rejected.
+    clock.advance(wait - Duration::from_millis(1));
+    assert!(lim.try_run(|| unreachable!()).is_err());
+
+    // Once the interval has fully elapsed, the call is paced through.
+    clock.advance(Duration::from_millis(1));
+    assert!(lim.try_run(|| ()).is_ok());
+
+    // Immediately after that, we're back to paced (no more burst credit).
+    let wait = lim.try_run(|| unreachable!()).unwrap_err();
+    assert_eq!(wait, Duration::from_millis(500));
+}
+
+#[test]
+fn test_take_stats_resets_dropped_count() {
+    let clock = FakeClock::new();
+    let mut lim = RateLimiter::with_quota_and_clock(1, Duration::from_millis(100), clock);
+
+    assert!(lim.try_run(|| ()).is_ok());
+    assert_eq!(lim.dropped(), 0);
+
+    assert!(lim.try_run(|| unreachable!()).is_err());
+    assert!(lim.try_run(|| unreachable!()).is_err());
+    assert_eq!(lim.dropped(), 2);
+
+    let stats = lim.take_stats();
+    assert_eq!(stats.dropped(), 2);
+    assert_eq!(stats.to_string(), "2 dropped calls");
+
+    // The counter (and a fresh snapshot) resets to zero.
+    assert_eq!(lim.dropped(), 0);
+    assert_eq!(lim.take_stats().dropped(), 0);
+}
+
 #[test]
 fn test_timer() {
     let _t = Timer::start(|elapsed| eprintln!("slept for {elapsed:?}"));
     thread::sleep(Duration::from_millis(10));
 }
+
+#[test]
+fn test_throttled_reader_caps_throughput() {
+    // The budget starts full (100 bytes free immediately), so the remaining
+    // 50 bytes of a 150-byte read must wait out ~500ms at 100 bytes/sec.
+    let data = [0u8; 150];
+    let mut reader = ThrottledReader::new(&data[..], 100);
+
+    let start = std::time::Instant::now();
+    let mut buf = [0u8; 64];
+    let mut total = 0;
+    while total < data.len() {
+        total += reader.read(&mut buf).unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(400));
+}
+
+#[test]
+fn test_throttled_writer_caps_throughput() {
+    let data = [0u8; 150];
+    let mut writer = ThrottledWriter::new(Vec::new(), 100);
+
+    let start = std::time::Instant::now();
+    let mut written = 0;
+    while written < data.len() {
+        written += writer.write(&data[written..]).unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(400));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_run_after_ready_paces_and_returns_value() {
+    let cooldown = Duration::from_millis(50);
+    let mut lim = RateLimiter::new(cooldown);
+
+    // Cold: resolves immediately with the closure's value.
+    assert_eq!(lim.run_after_ready(|| 1).await, 1);
+
+    // Hot: has to actually sleep out the cooldown before resolving.
+    let start = std::time::Instant::now();
+    assert_eq!(lim.run_after_ready(|| 2).await, 2);
+    assert!(start.elapsed() >= cooldown);
+}